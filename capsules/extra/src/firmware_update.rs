@@ -0,0 +1,269 @@
+// Licensed under the Apache License, Version 2.0 or the MIT License.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright Tock Contributors 2022.
+
+//! Dual-bank A/B firmware update
+//!
+//! This capsule lets a privileged process stage a new firmware image into
+//! whichever flash bank isn't currently running, verify it, and then
+//! request a bank swap and reset so the new image boots next.
+//!
+//! It only talks to the underlying flash through the [`DualBankFlash`]
+//! trait, so it stays chip-agnostic; a board wires it to a chip's
+//! dual-bank flash support (e.g.
+//! `stm32f4xx::chip_specific::flash_specific::dual_bank` on the F42x/F43x
+//! parts).
+
+use kernel::grant::{AllowRoCount, AllowRwCount, AllowUrCount, Grant, UpcallCount};
+use kernel::process::{self, ProcessId};
+use kernel::syscall::{CommandReturn, SyscallDriver};
+use kernel::ErrorCode;
+
+/// Syscall driver number.
+pub const DRIVER_NUM: usize = capsules_core::driver::NUM::FirmwareUpdate as usize;
+
+/// Largest chunk of a staged image this capsule will copy out of a
+/// process's allowed buffer in a single `command` call.
+const MAX_CHUNK_LEN: usize = 512;
+
+/// Metadata written at the start of each flash bank/slot so a minimal boot
+/// path can pick the newest valid image.
+///
+/// This is the capsule-side definition a [`DualBankFlash`] implementation
+/// is written in terms of. A chip crate cannot depend on this one (capsules
+/// depend on chips, never the reverse), so e.g.
+/// `stm32f4xx::chip_specific::flash_specific::dual_bank::SlotHeader` is a
+/// structurally identical copy rather than a re-export; a board converts
+/// between the two with a field-by-field copy when it wires that module's
+/// functions into this trait.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SlotHeader {
+    /// Length in bytes of the staged image.
+    pub image_length: u32,
+    /// CRC32 of the staged image, used to detect a torn write.
+    pub crc32: u32,
+    /// Monotonically increasing version, used to pick the newest valid
+    /// image when both banks are valid.
+    pub version: u32,
+    /// Whether this header (and the image it describes) should be
+    /// trusted.
+    pub valid: bool,
+}
+
+impl SlotHeader {
+    /// Size in bytes of the serialized header.
+    pub const SIZE: usize = 16;
+
+    const VALID_MAGIC: u32 = 0x5343_4B31;
+
+    /// Builds a header for a freshly staged, valid image.
+    pub fn new(image_length: u32, crc32: u32, version: u32) -> Self {
+        Self {
+            image_length,
+            crc32,
+            version,
+            valid: true,
+        }
+    }
+
+    /// Serializes this header the way it is written at the start of a
+    /// bank.
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..4].copy_from_slice(&self.image_length.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.crc32.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.version.to_le_bytes());
+        let valid_marker = if self.valid { Self::VALID_MAGIC } else { 0 };
+        bytes[12..16].copy_from_slice(&valid_marker.to_le_bytes());
+        bytes
+    }
+
+    /// Parses a header previously written by [`SlotHeader::to_bytes`].
+    pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Self {
+        Self {
+            image_length: u32::from_le_bytes(bytes[0..4].try_into().unwrap()),
+            crc32: u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+            version: u32::from_le_bytes(bytes[8..12].try_into().unwrap()),
+            valid: u32::from_le_bytes(bytes[12..16].try_into().unwrap()) == Self::VALID_MAGIC,
+        }
+    }
+}
+
+/// Chip-specific hook this capsule drives to actually touch the inactive
+/// flash bank. Implemented by a chip's dual-bank flash support and wired
+/// to this capsule by the board.
+pub trait DualBankFlash {
+    /// Size in bytes of the inactive bank, i.e. the largest image that can
+    /// be staged.
+    fn inactive_bank_capacity(&self) -> usize;
+
+    /// Erases the inactive bank so it can be reprogrammed from scratch.
+    fn erase_inactive_bank(&self) -> Result<(), ErrorCode>;
+
+    /// Programs `data` at `offset` bytes into the inactive bank.
+    fn program_inactive_bank(&self, offset: usize, data: &[u8]) -> Result<(), ErrorCode>;
+
+    /// Reads the header currently stored at the start of the *active*
+    /// bank, if any, so a freshly staged image can derive the next
+    /// version number. Returns `None` if the active bank has no valid
+    /// header (e.g. a first boot with no prior update).
+    fn active_bank_header(&self) -> Option<SlotHeader>;
+
+    /// Recomputes the CRC32 over the first `image_length` bytes actually
+    /// written to the inactive bank and reports whether it matches
+    /// `expected_crc32`. Must be called (and must return `Ok(true)`)
+    /// before [`DualBankFlash::mark_inactive_bank_valid`], so a torn or
+    /// incomplete write can never be accepted as a valid image.
+    fn verify_inactive_bank(
+        &self,
+        image_length: usize,
+        expected_crc32: u32,
+    ) -> Result<bool, ErrorCode>;
+
+    /// Writes `header` marking the inactive bank as holding a complete,
+    /// verified image, so a minimal boot path can pick it up.
+    fn mark_inactive_bank_valid(&self, header: &SlotHeader) -> Result<(), ErrorCode>;
+
+    /// Flips the boot-bank-swap bit and resets the device so the inactive
+    /// bank (now marked valid) boots next.
+    fn swap_and_reset(&self) -> Result<(), ErrorCode>;
+}
+
+pub struct FirmwareUpdateDriver<'a, F: DualBankFlash> {
+    flash: &'a F,
+
+    apps: Grant<App, UpcallCount<0>, AllowRoCount<1>, AllowRwCount<0>, AllowUrCount<0>>,
+}
+
+impl<'a, F: DualBankFlash> FirmwareUpdateDriver<'a, F> {
+    pub fn new(
+        flash: &'a F,
+        grant: Grant<App, UpcallCount<0>, AllowRoCount<1>, AllowRwCount<0>, AllowUrCount<0>>,
+    ) -> Self {
+        Self { flash, apps: grant }
+    }
+
+    /// Copies up to `MAX_CHUNK_LEN` bytes out of the process's read-only
+    /// allow slot 0 and programs them at `offset` into the inactive bank.
+    ///
+    /// `offset` comes straight from the process via `command`'s `data1`, so
+    /// it is validated against [`DualBankFlash::inactive_bank_capacity`]
+    /// here, before any flash access: the capsule is the privilege boundary
+    /// and must not rely on the chip-specific implementation underneath it
+    /// to reject an out-of-range offset.
+    fn stage_chunk(&self, processid: ProcessId, offset: usize) -> Result<(), ErrorCode> {
+        self.apps
+            .enter(processid, |_app, kernel_data| {
+                kernel_data
+                    .get_readonly_processbuffer(0)
+                    .map_err(|_| ErrorCode::NOMEM)
+                    .and_then(|buffer| {
+                        buffer
+                            .enter(|chunk| {
+                                let mut staging = [0; MAX_CHUNK_LEN];
+                                let len = chunk.len().min(MAX_CHUNK_LEN);
+                                let capacity = self.flash.inactive_bank_capacity();
+                                if offset > capacity || len > capacity - offset {
+                                    return Err(ErrorCode::INVAL);
+                                }
+                                chunk[..len].copy_to_slice(&mut staging[..len]);
+                                self.flash.program_inactive_bank(offset, &staging[..len])
+                            })
+                            .unwrap_or(Err(ErrorCode::NOMEM))
+                    })
+            })
+            .unwrap_or(Err(ErrorCode::FAIL))
+    }
+}
+
+impl<'a, F: DualBankFlash> SyscallDriver for FirmwareUpdateDriver<'a, F> {
+    /// Commands for FirmwareUpdateDriver.
+    ///
+    /// ### `command_num`
+    ///
+    /// - `0`: Driver existence check.
+    /// - `1`: Get the capacity (in bytes) of the inactive bank.
+    /// - `2`: Erase the inactive bank.
+    /// - `3`: Program the chunk in read-only allow slot 0 at byte offset
+    ///   `data1` of the inactive bank.
+    /// - `4`: Verify the staged image (`data1` bytes long, CRC32 `data2`)
+    ///   against what was actually written, and mark it valid.
+    /// - `5`: Swap to the inactive bank and reset.
+    fn command(
+        &self,
+        command_number: usize,
+        data1: usize,
+        data2: usize,
+        processid: ProcessId,
+    ) -> CommandReturn {
+        match command_number {
+            // Check existence
+            0 => CommandReturn::success(),
+
+            // Get inactive bank capacity
+            1 => CommandReturn::success_u32(self.flash.inactive_bank_capacity() as u32),
+
+            // Erase the inactive bank
+            2 => match self.flash.erase_inactive_bank() {
+                Ok(()) => CommandReturn::success(),
+                Err(e) => CommandReturn::failure(e),
+            },
+
+            // Stage a chunk at the given offset
+            3 => match self.stage_chunk(processid, data1) {
+                Ok(()) => CommandReturn::success(),
+                Err(e) => CommandReturn::failure(e),
+            },
+
+            // Verify the staged image, then mark it valid
+            4 => match self.flash.verify_inactive_bank(data1, data2 as u32) {
+                Ok(true) => {
+                    let version = self
+                        .flash
+                        .active_bank_header()
+                        .map_or(1, |header| header.version.wrapping_add(1));
+                    let header = SlotHeader::new(data1 as u32, data2 as u32, version);
+                    match self.flash.mark_inactive_bank_valid(&header) {
+                        Ok(()) => CommandReturn::success(),
+                        Err(e) => CommandReturn::failure(e),
+                    }
+                }
+                Ok(false) => CommandReturn::failure(ErrorCode::FAIL),
+                Err(e) => CommandReturn::failure(e),
+            },
+
+            // Swap banks and reset
+            5 => match self.flash.swap_and_reset() {
+                Ok(()) => CommandReturn::success(),
+                Err(e) => CommandReturn::failure(e),
+            },
+
+            // default
+            _ => CommandReturn::failure(ErrorCode::NOSUPPORT),
+        }
+    }
+
+    fn allocate_grant(&self, processid: ProcessId) -> Result<(), process::Error> {
+        self.apps.enter(processid, |_, _| {})
+    }
+}
+
+#[derive(Default)]
+pub struct App {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_header_round_trip() {
+        let header = SlotHeader::new(0x0004_2000, 0xDEAD_BEEF, 7);
+        let bytes = header.to_bytes();
+        assert_eq!(header, SlotHeader::from_bytes(&bytes));
+
+        let mut invalid = header;
+        invalid.valid = false;
+        assert_eq!(invalid, SlotHeader::from_bytes(&invalid.to_bytes()));
+        assert_ne!(header.to_bytes(), invalid.to_bytes());
+    }
+}
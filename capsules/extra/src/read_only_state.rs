@@ -30,6 +30,19 @@
 //!   |                         |
 //!   |     Time Ticks (u64)    |
 //!   |-------------------------|
+//!
+//! Version 2 (adds a PTP network-time field after Version 1):
+//!   |-------------------------|
+//!   |    Switch Count (u32)   |
+//!   |-------------------------|
+//!   |   Pending Tasks (u32)   |
+//!   |-------------------------|
+//!   |                         |
+//!   |     Time Ticks (u64)    |
+//!   |-------------------------|
+//!   |                         |
+//!   |  PTP Network Time (u64) |
+//!   |-------------------------|
 //! ```
 
 use core::cell::Cell;
@@ -39,14 +52,25 @@ use kernel::platform::ContextSwitchCallback;
 use kernel::process::{self, ProcessId};
 use kernel::processbuffer::WriteableProcessBuffer;
 use kernel::syscall::{CommandReturn, SyscallDriver};
+use kernel::utilities::cells::OptionalCell;
 use kernel::ErrorCode;
 
 /// Syscall driver number.
 pub const DRIVER_NUM: usize = capsules_core::driver::NUM::ReadOnlyState as usize;
-const VERSION: u32 = 1;
+const VERSION: u32 = 2;
+
+/// Source of the last hardware PTP timestamp captured by a network
+/// peripheral, e.g. an Ethernet MAC with an IEEE 1588 timestamp unit. Wired
+/// up by the board so userspace can read network time without a syscall.
+pub trait PtpClock {
+    /// The last PTP hardware timestamp captured, in raw ticks, or `None` if
+    /// nothing has been timestamped yet.
+    fn now_ticks(&self) -> Option<u64>;
+}
 
 pub struct ReadOnlyStateDriver<'a, T: Time> {
     timer: &'a T,
+    ptp: OptionalCell<&'a dyn PtpClock>,
 
     apps: Grant<App, UpcallCount<0>, AllowRoCount<0>, AllowRwCount<0>, AllowUrCount<1>>,
 }
@@ -56,7 +80,17 @@ impl<'a, T: Time> ReadOnlyStateDriver<'a, T> {
         timer: &'a T,
         grant: Grant<App, UpcallCount<0>, AllowRoCount<0>, AllowRwCount<0>, AllowUrCount<1>>,
     ) -> ReadOnlyStateDriver<'a, T> {
-        ReadOnlyStateDriver { timer, apps: grant }
+        ReadOnlyStateDriver {
+            timer,
+            ptp: OptionalCell::empty(),
+            apps: grant,
+        }
+    }
+
+    /// Wires up the PTP network-time field (version 2 of the layout) to
+    /// `ptp`. Without a call to this, the PTP Network Time field stays 0.
+    pub fn set_ptp_clock(&self, ptp: &'a dyn PtpClock) {
+        self.ptp.set(ptp);
     }
 }
 
@@ -82,6 +116,10 @@ impl<'a, T: Time> ContextSwitchCallback for ReadOnlyStateDriver<'a, T> {
                             let now = self.timer.now().into_usize() as u64;
                             buf[8..16].copy_from_slice(&now.to_le_bytes());
                         }
+                        if buf.len() >= 24 {
+                            let ptp_time = self.ptp.and_then(|ptp| ptp.now_ticks()).unwrap_or(0);
+                            buf[16..24].copy_from_slice(&ptp_time.to_le_bytes());
+                        }
                     });
 
                 app.count.set(count.wrapping_add(1));
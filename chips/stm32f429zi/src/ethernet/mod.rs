@@ -0,0 +1,674 @@
+// Licensed under the Apache License, Version 2.0 or the MIT License.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright Tock Contributors 2022.
+
+//! STM32F429 Ethernet MAC + DMA driver.
+//!
+//! This module drives the STM32F429ZI Ethernet peripheral (MAC, MMC and DMA
+//! sub-blocks) well enough to move Ethernet II frames on and off the wire.
+//! The DMA engine on this peripheral is a ring-buffer based bus master: the
+//! CPU never touches frame data directly, it only owns a ring of small
+//! descriptors that hand buffers back and forth with the DMA engine. Each
+//! descriptor has an OWN bit that records who is allowed to touch it right
+//! now (the CPU or the DMA engine), a status/length word, a pointer to the
+//! data buffer, and a pointer to the next descriptor in the ring.
+//!
+//! To transmit, [`Ethernet::transmit`] copies the frame into the next free
+//! TX buffer, fills in the descriptor and sets its OWN bit, then pokes the
+//! DMA transmit poll demand register so the engine re-reads the ring.  To
+//! receive, [`Ethernet::handle_interrupt`] walks the RX ring starting from
+//! the last descriptor it owns; any descriptor whose OWN bit has been
+//! cleared by hardware holds a completed frame, which is delivered to the
+//! registered [`EthernetReceiveClient`] before the descriptor is recycled
+//! back to the DMA engine.
+//!
+//! The peripheral also has a PTP (IEEE 1588) timestamp unit: with the
+//! enhanced 8-word descriptor format selected and its subsecond counter
+//! running, the last two words of every descriptor hold the hardware
+//! timestamp of the frame it carried. This driver selects that format and
+//! enables the counter in [`Ethernet::init`] and surfaces the last captured
+//! ingress/egress timestamp both through the HIL callbacks and through
+//! [`Ethernet::last_rx_timestamp`]/[`Ethernet::last_tx_timestamp`], as well
+//! as [`Ethernet::now_ticks`] for boards that want to wire this driver
+//! into `capsules_extra::read_only_state::PtpClock`.
+
+pub mod utils;
+
+use kernel::utilities::cells::OptionalCell;
+use kernel::utilities::registers::interfaces::{Readable, Writeable};
+use kernel::utilities::registers::{register_bitfields, register_structs, ReadWrite, WriteOnly};
+use kernel::utilities::StaticRef;
+use kernel::ErrorCode;
+
+use core::cell::Cell;
+
+pub use self::utils::{EthernetSpeed, MacAddress, OperationMode};
+
+/// Base address of the ETH peripheral on the STM32F429ZI.
+const ETH_BASE: StaticRef<EthRegisters> =
+    unsafe { StaticRef::new(0x4002_8000 as *const EthRegisters) };
+
+register_structs! {
+    /// ETH MAC + DMA registers
+    EthRegisters {
+        (0x000 => maccr: ReadWrite<u32, MACCR::Register>),
+        (0x004 => macffr: ReadWrite<u32, MACFFR::Register>),
+        (0x008 => _reserved0),
+        (0x040 => maca0hr: ReadWrite<u32, MACA0HR::Register>),
+        (0x044 => maca0lr: ReadWrite<u32>),
+        (0x048 => _reserved1),
+        (0x700 => ptptscr: ReadWrite<u32, PTPTSCR::Register>),
+        (0x704 => ptpssir: ReadWrite<u32>),
+        (0x708 => ptptshr: ReadWrite<u32>),
+        (0x70C => ptptslr: ReadWrite<u32>),
+        (0x710 => _reserved2),
+        (0x1000 => dmabmr: ReadWrite<u32, DMABMR::Register>),
+        (0x1004 => dmatpdr: WriteOnly<u32>),
+        (0x1008 => dmarpdr: WriteOnly<u32>),
+        (0x100C => dmardlar: ReadWrite<u32>),
+        (0x1010 => dmatdlar: ReadWrite<u32>),
+        (0x1014 => dmasr: ReadWrite<u32, DMASR::Register>),
+        (0x1018 => dmaomr: ReadWrite<u32, DMAOMR::Register>),
+        (0x101C => dmaier: ReadWrite<u32, DMAIER::Register>),
+        (0x1020 => @END),
+    }
+}
+
+register_bitfields![u32,
+    MACCR [
+        RE OFFSET(2) NUMBITS(1) [],
+        TE OFFSET(3) NUMBITS(1) [],
+        DM OFFSET(11) NUMBITS(1) [],
+        FES OFFSET(14) NUMBITS(1) [],
+    ],
+    MACFFR [
+        PM OFFSET(4) NUMBITS(1) [],
+    ],
+    MACA0HR [
+        MACA0H OFFSET(0) NUMBITS(16) [],
+    ],
+    PTPTSCR [
+        TSE OFFSET(0) NUMBITS(1) [],
+        TSFCU OFFSET(1) NUMBITS(1) [],
+    ],
+    DMABMR [
+        SR OFFSET(0) NUMBITS(1) [],
+        DA OFFSET(1) NUMBITS(1) [],
+        EDFE OFFSET(7) NUMBITS(1) [],
+    ],
+    DMASR [
+        TS OFFSET(0) NUMBITS(1) [],
+        RS OFFSET(6) NUMBITS(1) [],
+        NIS OFFSET(16) NUMBITS(1) [],
+        AIS OFFSET(15) NUMBITS(1) [],
+    ],
+    DMAOMR [
+        SR OFFSET(1) NUMBITS(1) [],
+        ST OFFSET(13) NUMBITS(1) [],
+    ],
+    DMAIER [
+        TIE OFFSET(0) NUMBITS(1) [],
+        RIE OFFSET(6) NUMBITS(1) [],
+        NISE OFFSET(16) NUMBITS(1) [],
+    ],
+];
+
+/// Number of descriptors (and buffers) in each ring.
+pub const TX_DESC_COUNT: usize = 4;
+pub const RX_DESC_COUNT: usize = 4;
+
+/// Large enough to hold a maximum-size untagged Ethernet II frame.
+pub const BUFFER_SIZE: usize = 1536;
+
+/// Backing storage for the TX descriptor ring's buffers, sized and typed so
+/// a board can allocate it with `static_init!`/`static_buf!` and hand it to
+/// [`Ethernet::new`] rather than this driver embedding a multi-kilobyte
+/// array in its own struct (and on its own stack frame while it is being
+/// built).
+pub type TxBuffers = [[u8; BUFFER_SIZE]; TX_DESC_COUNT];
+
+/// Backing storage for the RX descriptor ring's buffers. See [`TxBuffers`].
+pub type RxBuffers = [[u8; BUFFER_SIZE]; RX_DESC_COUNT];
+
+const TDES0_OWN: u32 = 1 << 31;
+const TDES0_IC: u32 = 1 << 30;
+const TDES0_LS: u32 = 1 << 29;
+const TDES0_FS: u32 = 1 << 28;
+const TDES0_TCH: u32 = 1 << 20;
+
+const TDES1_BUFFER_SIZE_MASK: u32 = 0x1FFF;
+
+const RDES0_OWN: u32 = 1 << 31;
+/// Error summary: set by hardware when any of the CRC, dribble-bit,
+/// receive, watchdog-timeout, late-collision or overflow error bits in
+/// this same word is set, so software only has to check one bit to
+/// decide whether the frame is trustworthy.
+const RDES0_ES: u32 = 1 << 15;
+const RDES0_FL_SHIFT: u32 = 16;
+const RDES0_FL_MASK: u32 = 0x3FFF << RDES0_FL_SHIFT;
+
+const RDES1_RCH: u32 = 1 << 14;
+const RDES1_RBS1_MASK: u32 = 0x1FFF;
+
+/// Subsecond increment value programmed into `PTPSSIR`, assuming a 50MHz
+/// HCLK reference to the PTP subsecond counter: each tick advances the
+/// subsecond register by this many nanoseconds.
+const PTP_SUBSECOND_INCREMENT_NS: u32 = 20;
+
+/// A single entry in the TX descriptor ring, in the "enhanced" 8-word
+/// format that the PTP timestamp unit requires.
+///
+/// Laid out exactly as the DMA engine expects it in memory: the first word
+/// is status/control (including the OWN bit), the second is the buffer
+/// byte-count, the third is the data buffer address and the fourth is the
+/// address of the next descriptor (since we always run in chained mode).
+/// `tdes4`/`tdes5` are reserved, and `tdes6`/`tdes7` hold the low/high
+/// words of the hardware egress timestamp once the frame has been sent.
+#[repr(C)]
+struct TxDescriptor {
+    tdes0: u32,
+    tdes1: u32,
+    tdes2: u32,
+    tdes3: u32,
+    tdes4: u32,
+    tdes5: u32,
+    tdes6: u32,
+    tdes7: u32,
+}
+
+impl TxDescriptor {
+    const fn new() -> Self {
+        Self {
+            tdes0: 0,
+            tdes1: 0,
+            tdes2: 0,
+            tdes3: 0,
+            tdes4: 0,
+            tdes5: 0,
+            tdes6: 0,
+            tdes7: 0,
+        }
+    }
+
+    fn is_owned_by_dma(&self) -> bool {
+        // SAFETY: descriptors are shared with the DMA engine, so every
+        // access to a live field must be volatile.
+        unsafe { core::ptr::read_volatile(&self.tdes0) & TDES0_OWN != 0 }
+    }
+
+    /// Combines the low/high timestamp words hardware stamped into this
+    /// descriptor once it transmitted, as raw PTP subsecond-counter ticks.
+    fn timestamp(&self) -> u64 {
+        let low = unsafe { core::ptr::read_volatile(&self.tdes6) };
+        let high = unsafe { core::ptr::read_volatile(&self.tdes7) };
+        ((high as u64) << 32) | low as u64
+    }
+}
+
+/// A single entry in the RX descriptor ring. Same chained, enhanced 8-word
+/// layout as [`TxDescriptor`], but the status bits in `rdes0` are
+/// hardware-written, and `rdes6`/`rdes7` hold the ingress timestamp.
+#[repr(C)]
+struct RxDescriptor {
+    rdes0: u32,
+    rdes1: u32,
+    rdes2: u32,
+    rdes3: u32,
+    rdes4: u32,
+    rdes5: u32,
+    rdes6: u32,
+    rdes7: u32,
+}
+
+impl RxDescriptor {
+    const fn new() -> Self {
+        Self {
+            rdes0: 0,
+            rdes1: 0,
+            rdes2: 0,
+            rdes3: 0,
+            rdes4: 0,
+            rdes5: 0,
+            rdes6: 0,
+            rdes7: 0,
+        }
+    }
+
+    fn is_owned_by_dma(&self) -> bool {
+        unsafe { core::ptr::read_volatile(&self.rdes0) & RDES0_OWN != 0 }
+    }
+
+    fn frame_length(&self) -> usize {
+        let rdes0 = unsafe { core::ptr::read_volatile(&self.rdes0) };
+        ((rdes0 & RDES0_FL_MASK) >> RDES0_FL_SHIFT) as usize
+    }
+
+    /// Whether hardware flagged this frame as errored (CRC error,
+    /// too-long/too-short, late collision, watchdog timeout, ...). An
+    /// errored frame's buffer must not be handed to a client.
+    fn has_error(&self) -> bool {
+        unsafe { core::ptr::read_volatile(&self.rdes0) & RDES0_ES != 0 }
+    }
+
+    /// Combines the low/high timestamp words hardware stamped into this
+    /// descriptor on ingress, as raw PTP subsecond-counter ticks.
+    fn timestamp(&self) -> u64 {
+        let low = unsafe { core::ptr::read_volatile(&self.rdes6) };
+        let high = unsafe { core::ptr::read_volatile(&self.rdes7) };
+        ((high as u64) << 32) | low as u64
+    }
+
+    fn give_to_dma(&self) {
+        unsafe {
+            core::ptr::write_volatile(
+                &mut (*(self as *const _ as *mut RxDescriptor)).rdes0,
+                RDES0_OWN,
+            );
+        }
+    }
+}
+
+/// Receive-side client of the Ethernet HIL: notified whenever a complete
+/// frame has been pulled off the RX ring.
+pub trait EthernetReceiveClient {
+    /// `frame` is the raw Ethernet II frame (destination MAC, source MAC,
+    /// ethertype and payload), `mac_address` the address the driver is
+    /// currently configured to accept unicast traffic for, and
+    /// `timestamp_ticks` the PTP hardware timestamp captured on ingress (in
+    /// raw subsecond-counter ticks), if the timestamp unit is enabled.
+    fn received_frame(&self, frame: &[u8], mac_address: MacAddress, timestamp_ticks: Option<u64>);
+}
+
+/// Transmit-side client of the Ethernet HIL: notified once a frame handed
+/// to [`Ethernet::transmit`] has actually left the TX ring.
+pub trait EthernetTransmitClient {
+    /// `timestamp_ticks` is the PTP hardware timestamp captured on egress
+    /// (in raw subsecond-counter ticks), if the timestamp unit is enabled.
+    fn transmit_done(&self, result: Result<(), ErrorCode>, timestamp_ticks: Option<u64>);
+}
+
+/// STM32F429 Ethernet MAC + DMA driver.
+pub struct Ethernet<'a> {
+    registers: StaticRef<EthRegisters>,
+    rcc: &'a crate::rcc::Rcc,
+
+    mac_address: Cell<MacAddress>,
+    operation_mode: Cell<OperationMode>,
+    speed: Cell<EthernetSpeed>,
+
+    tx_descriptors: [TxDescriptor; TX_DESC_COUNT],
+    tx_buffers: &'static mut TxBuffers,
+    next_tx: Cell<usize>,
+    next_tx_complete: Cell<usize>,
+
+    rx_descriptors: [RxDescriptor; RX_DESC_COUNT],
+    rx_buffers: &'static mut RxBuffers,
+    next_rx: Cell<usize>,
+
+    tx_client: OptionalCell<&'a dyn EthernetTransmitClient>,
+    rx_client: OptionalCell<&'a dyn EthernetReceiveClient>,
+
+    last_rx_timestamp: Cell<Option<u64>>,
+    last_tx_timestamp: Cell<Option<u64>>,
+    last_rx_timestamp_seq: Cell<u64>,
+    last_tx_timestamp_seq: Cell<u64>,
+    next_timestamp_seq: Cell<u64>,
+}
+
+impl<'a> Ethernet<'a> {
+    /// `tx_buffers`/`rx_buffers` are board-allocated (typically via
+    /// `static_init!`/`static_buf!`) rather than owned by this struct: at
+    /// `TX_DESC_COUNT * BUFFER_SIZE` bytes apiece, embedding them directly
+    /// would mean moving several kilobytes by value through this
+    /// constructor's stack frame.
+    pub fn new(
+        rcc: &'a crate::rcc::Rcc,
+        tx_buffers: &'static mut TxBuffers,
+        rx_buffers: &'static mut RxBuffers,
+    ) -> Self {
+        Self {
+            registers: ETH_BASE,
+            rcc,
+            mac_address: Cell::new(MacAddress::default()),
+            operation_mode: Cell::new(OperationMode::FullDuplex),
+            speed: Cell::new(EthernetSpeed::Speed100Mbs),
+            tx_descriptors: [
+                TxDescriptor::new(),
+                TxDescriptor::new(),
+                TxDescriptor::new(),
+                TxDescriptor::new(),
+            ],
+            tx_buffers,
+            next_tx: Cell::new(0),
+            next_tx_complete: Cell::new(0),
+            rx_descriptors: [
+                RxDescriptor::new(),
+                RxDescriptor::new(),
+                RxDescriptor::new(),
+                RxDescriptor::new(),
+            ],
+            rx_buffers,
+            next_rx: Cell::new(0),
+            tx_client: OptionalCell::empty(),
+            rx_client: OptionalCell::empty(),
+            last_rx_timestamp: Cell::new(None),
+            last_tx_timestamp: Cell::new(None),
+            last_rx_timestamp_seq: Cell::new(0),
+            last_tx_timestamp_seq: Cell::new(0),
+            next_timestamp_seq: Cell::new(0),
+        }
+    }
+
+    /// Brings up the MAC clocks, builds the TX/RX descriptor rings and
+    /// starts the DMA engine. Must run before any `transmit()` calls or
+    /// `handle_interrupt()` will do anything meaningful.
+    pub fn init(&self) {
+        self.rcc.enable_ethernet_mac_clock();
+        self.rcc.enable_ethernet_mac_tx_clock();
+        self.rcc.enable_ethernet_mac_rx_clock();
+
+        self.init_descriptor_rings();
+
+        self.registers
+            .dmardlar
+            .set(self.rx_descriptors.as_ptr() as u32);
+        self.registers
+            .dmatdlar
+            .set(self.tx_descriptors.as_ptr() as u32);
+
+        self.apply_link_settings();
+
+        // Promiscuous mode stays off: RX filtering (broadcast, multicast,
+        // or unicast to our own address) is enforced in software by
+        // `deliver_if_accepted`, and that logic assumes hardware never
+        // hands up frames addressed to somebody else.
+        self.registers.macffr.modify(MACFFR::PM::CLEAR);
+
+        self.registers.maccr.modify(MACCR::TE::SET + MACCR::RE::SET);
+        self.registers
+            .dmaomr
+            .modify(DMAOMR::SR::SET + DMAOMR::ST::SET);
+
+        self.registers
+            .dmaier
+            .modify(DMAIER::TIE::SET + DMAIER::RIE::SET + DMAIER::NISE::SET);
+
+        // Enhanced descriptors are required for the DMA engine to write
+        // PTP timestamps into tdes6/7 and rdes6/7.
+        self.registers.dmabmr.modify(DMABMR::EDFE::SET);
+        self.enable_ptp_timestamping();
+    }
+
+    /// Starts the PTP subsecond counter so every TX/RX descriptor gets
+    /// stamped with a hardware timestamp.
+    fn enable_ptp_timestamping(&self) {
+        self.registers.ptptscr.modify(PTPTSCR::TSE::SET);
+        self.registers.ptpssir.set(PTP_SUBSECOND_INCREMENT_NS);
+        // Fine-update mode: the subsecond register advances by
+        // `ptpssir` every reference clock tick.
+        self.registers.ptptscr.modify(PTPTSCR::TSFCU::SET);
+    }
+
+    /// Wires up the chained TX/RX rings: every descriptor's `tdes3`/`rdes3`
+    /// points at the next one, wrapping back to the first at the end, and
+    /// every descriptor's buffer pointer is set to its matching static
+    /// buffer.
+    fn init_descriptor_rings(&self) {
+        for i in 0..TX_DESC_COUNT {
+            let next = &self.tx_descriptors[(i + 1) % TX_DESC_COUNT] as *const _ as u32;
+            let buffer = self.tx_buffers[i].as_ptr() as u32;
+            let descriptor = &self.tx_descriptors[i];
+            unsafe {
+                core::ptr::write_volatile(&descriptor.tdes0 as *const _ as *mut u32, TDES0_TCH);
+                core::ptr::write_volatile(&descriptor.tdes1 as *const _ as *mut u32, 0);
+                core::ptr::write_volatile(&descriptor.tdes2 as *const _ as *mut u32, buffer);
+                core::ptr::write_volatile(&descriptor.tdes3 as *const _ as *mut u32, next);
+            }
+        }
+
+        for i in 0..RX_DESC_COUNT {
+            let next = &self.rx_descriptors[(i + 1) % RX_DESC_COUNT] as *const _ as u32;
+            let buffer = self.rx_buffers[i].as_ptr() as u32;
+            let descriptor = &self.rx_descriptors[i];
+            unsafe {
+                core::ptr::write_volatile(&descriptor.rdes0 as *const _ as *mut u32, RDES0_OWN);
+                core::ptr::write_volatile(
+                    &descriptor.rdes1 as *const _ as *mut u32,
+                    RDES1_RCH | (BUFFER_SIZE as u32 & RDES1_RBS1_MASK),
+                );
+                core::ptr::write_volatile(&descriptor.rdes2 as *const _ as *mut u32, buffer);
+                core::ptr::write_volatile(&descriptor.rdes3 as *const _ as *mut u32, next);
+            }
+        }
+    }
+
+    /// Configures the current [`MacAddress`] the MAC should accept unicast
+    /// frames for. Takes effect immediately: the MAC address registers and
+    /// the software RX filter are both updated by this call.
+    pub fn set_mac_address(&self, mac_address: MacAddress) {
+        self.mac_address.set(mac_address);
+
+        let bytes = mac_address.0;
+        let low = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let high = u16::from_le_bytes([bytes[4], bytes[5]]);
+        self.registers.maca0lr.set(low);
+        self.registers
+            .maca0hr
+            .write(MACA0HR::MACA0H.val(high as u32));
+    }
+
+    pub fn mac_address(&self) -> MacAddress {
+        self.mac_address.get()
+    }
+
+    /// Records the result of auto-negotiation (duplex mode and link speed)
+    /// and pushes it into `MACCR` right away; [`Ethernet::init`] applies
+    /// the recorded settings again.
+    pub fn set_link_settings(&self, mode: OperationMode, speed: EthernetSpeed) {
+        self.operation_mode.set(mode);
+        self.speed.set(speed);
+        self.apply_link_settings();
+    }
+
+    fn apply_link_settings(&self) {
+        match self.operation_mode.get() {
+            OperationMode::FullDuplex => self.registers.maccr.modify(MACCR::DM::SET),
+            OperationMode::HalfDuplex => self.registers.maccr.modify(MACCR::DM::CLEAR),
+        }
+        match self.speed.get() {
+            EthernetSpeed::Speed100Mbs => self.registers.maccr.modify(MACCR::FES::SET),
+            EthernetSpeed::Speed10Mbs => self.registers.maccr.modify(MACCR::FES::CLEAR),
+        }
+    }
+
+    pub fn set_transmit_client(&self, client: &'a dyn EthernetTransmitClient) {
+        self.tx_client.set(client);
+    }
+
+    pub fn set_receive_client(&self, client: &'a dyn EthernetReceiveClient) {
+        self.rx_client.set(client);
+    }
+
+    /// Queues `frame` for transmission on the next free TX descriptor.
+    ///
+    /// Returns `BUSY` if every descriptor but one is already in flight
+    /// (one slot is always kept free so `next_tx` can never lap
+    /// `next_tx_complete`, which would otherwise make a full ring
+    /// indistinguishable from an empty one), and `SIZE` if the frame is
+    /// larger than a single descriptor buffer (jumbo / scatter-gather
+    /// frames are not supported).
+    pub fn transmit(&self, frame: &[u8]) -> Result<(), ErrorCode> {
+        if frame.len() > BUFFER_SIZE {
+            return Err(ErrorCode::SIZE);
+        }
+
+        let index = self.next_tx.get();
+        let next_index = (index + 1) % TX_DESC_COUNT;
+        if next_index == self.next_tx_complete.get() {
+            return Err(ErrorCode::BUSY);
+        }
+
+        let descriptor = &self.tx_descriptors[index];
+        if descriptor.is_owned_by_dma() {
+            return Err(ErrorCode::BUSY);
+        }
+
+        // Safe because we just confirmed the CPU owns this descriptor, so
+        // the DMA engine will not touch the backing buffer concurrently.
+        let buffer = unsafe {
+            core::slice::from_raw_parts_mut(self.tx_buffers[index].as_ptr() as *mut u8, frame.len())
+        };
+        buffer.copy_from_slice(frame);
+
+        unsafe {
+            core::ptr::write_volatile(
+                &descriptor.tdes1 as *const _ as *mut u32,
+                frame.len() as u32 & TDES1_BUFFER_SIZE_MASK,
+            );
+            // Hand the descriptor to the DMA engine last: once OWN is set,
+            // hardware may read it at any time.
+            core::ptr::write_volatile(
+                &descriptor.tdes0 as *const _ as *mut u32,
+                TDES0_TCH | TDES0_FS | TDES0_LS | TDES0_IC | TDES0_OWN,
+            );
+        }
+
+        // Poke the transmit poll demand register so the DMA engine resumes
+        // polling the ring if it had gone idle (the written value is
+        // ignored by hardware).
+        self.registers.dmatpdr.set(1);
+
+        self.next_tx.set(next_index);
+        Ok(())
+    }
+
+    /// Services the `ETH` NVIC interrupt: walks completed RX descriptors
+    /// and delivers their frames, then acknowledges completed TX
+    /// descriptors.
+    pub fn handle_interrupt(&self) {
+        let status = self.registers.dmasr.extract();
+
+        if status.is_set(DMASR::RS) {
+            self.service_rx_ring();
+        }
+
+        if status.is_set(DMASR::TS) {
+            self.service_tx_ring();
+        }
+
+        // Clear every status bit we just observed (write-1-to-clear).
+        self.registers.dmasr.set(status.get());
+    }
+
+    fn service_rx_ring(&self) {
+        loop {
+            let index = self.next_rx.get();
+            let descriptor = &self.rx_descriptors[index];
+            if descriptor.is_owned_by_dma() {
+                break;
+            }
+
+            if !descriptor.has_error() {
+                let length = descriptor.frame_length();
+                let frame = &self.rx_buffers[index][..length.min(BUFFER_SIZE)];
+                let timestamp = descriptor.timestamp();
+                self.last_rx_timestamp.set(Some(timestamp));
+                self.last_rx_timestamp_seq.set(self.next_timestamp_seq());
+                self.deliver_if_accepted(frame, timestamp);
+            }
+
+            descriptor.give_to_dma();
+            self.next_rx.set((index + 1) % RX_DESC_COUNT);
+        }
+    }
+
+    fn service_tx_ring(&self) {
+        loop {
+            let index = self.next_tx_complete.get();
+            if index == self.next_tx.get() {
+                // Caught up with every descriptor handed to the DMA engine.
+                break;
+            }
+
+            let descriptor = &self.tx_descriptors[index];
+            if descriptor.is_owned_by_dma() {
+                break;
+            }
+
+            let timestamp = descriptor.timestamp();
+            self.last_tx_timestamp.set(Some(timestamp));
+            self.last_tx_timestamp_seq.set(self.next_timestamp_seq());
+            self.tx_client
+                .map(|client| client.transmit_done(Ok(()), Some(timestamp)));
+
+            self.next_tx_complete.set((index + 1) % TX_DESC_COUNT);
+        }
+    }
+
+    /// Applies the MAC-address filtering rules a real MAC would apply in
+    /// hardware: accept broadcast, accept multicast, and accept unicast
+    /// only when it targets our own configured address.
+    fn deliver_if_accepted(&self, frame: &[u8], timestamp_ticks: u64) {
+        if frame.len() < 6 {
+            return;
+        }
+        let destination =
+            MacAddress::new([frame[0], frame[1], frame[2], frame[3], frame[4], frame[5]]);
+
+        let accepted = destination.is_broadcast()
+            || destination.is_multicast()
+            || (destination.is_unicast() && destination == self.mac_address.get());
+
+        if accepted {
+            self.rx_client.map(|client| {
+                client.received_frame(frame, self.mac_address.get(), Some(timestamp_ticks))
+            });
+        }
+    }
+
+    /// The PTP hardware timestamp (raw subsecond-counter ticks) captured
+    /// for the most recently received frame, or `None` if none has been
+    /// received yet.
+    pub fn last_rx_timestamp(&self) -> Option<u64> {
+        self.last_rx_timestamp.get()
+    }
+
+    /// The PTP hardware timestamp (raw subsecond-counter ticks) captured
+    /// for the most recently transmitted frame, or `None` if none has been
+    /// sent yet.
+    pub fn last_tx_timestamp(&self) -> Option<u64> {
+        self.last_tx_timestamp.get()
+    }
+
+    /// The PTP hardware timestamp (raw subsecond-counter ticks) captured
+    /// for whichever of the most recent RX or TX frame happened later, or
+    /// `None` if neither has happened yet.
+    ///
+    /// This is the single-value shape
+    /// `capsules_extra::read_only_state::PtpClock::now_ticks` expects, so
+    /// a board can wire this driver into that capsule with a one-line
+    /// trait impl that just forwards to this method.
+    pub fn now_ticks(&self) -> Option<u64> {
+        match (self.last_rx_timestamp.get(), self.last_tx_timestamp.get()) {
+            (Some(rx), Some(tx)) => {
+                if self.last_tx_timestamp_seq.get() >= self.last_rx_timestamp_seq.get() {
+                    Some(tx)
+                } else {
+                    Some(rx)
+                }
+            }
+            (Some(rx), None) => Some(rx),
+            (None, Some(tx)) => Some(tx),
+            (None, None) => None,
+        }
+    }
+
+    /// Hands out a fresh, monotonically increasing sequence number used to
+    /// order RX and TX timestamps relative to each other for
+    /// [`Ethernet::now_ticks`].
+    fn next_timestamp_seq(&self) -> u64 {
+        let seq = self.next_timestamp_seq.get().wrapping_add(1);
+        self.next_timestamp_seq.set(seq);
+        seq
+    }
+}
@@ -32,6 +32,54 @@ impl MacAddress {
     pub const fn is_unicast(&self) -> bool {
         !self.is_multicast() && !self.is_broadcast()
     }
+
+    /// Check whether the universal/local bit (bit 1 of the first byte) is
+    /// set, i.e. the address is locally administered rather than a
+    /// burned-in, globally-unique OUI assignment.
+    pub const fn is_locally_administered(&self) -> bool {
+        self.0[0] & 0x2 != 0
+    }
+
+    /// Derives the modified EUI-64 interface identifier for this address,
+    /// as used by IPv6 stateless address autoconfiguration: the OUI (first
+    /// 3 bytes) and NIC-specific (last 3 bytes) halves are split apart and
+    /// `0xFF, 0xFE` is inserted between them, then the universal/local bit
+    /// is flipped.
+    pub const fn to_eui64(&self) -> [u8; 8] {
+        let mac = self.0;
+        [
+            mac[0] ^ 0x2,
+            mac[1],
+            mac[2],
+            0xFF,
+            0xFE,
+            mac[3],
+            mac[4],
+            mac[5],
+        ]
+    }
+
+    /// Derives the `fe80::/64` IPv6 link-local address formed by prepending
+    /// the link-local prefix to this address's [`MacAddress::to_eui64`]
+    /// interface identifier.
+    pub const fn to_link_local_ipv6(&self) -> [u8; 16] {
+        let eui64 = self.to_eui64();
+        [
+            0xFE, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, eui64[0], eui64[1], eui64[2], eui64[3],
+            eui64[4], eui64[5], eui64[6], eui64[7],
+        ]
+    }
+
+    /// Synthesizes a stable, locally-administered unicast [`MacAddress`]
+    /// from `seed`, for boards without a burned-in hardware address. Clears
+    /// the multicast bit and sets the locally-administered bit so the
+    /// result is always a valid unicast address.
+    pub const fn locally_administered_unicast(seed: u64) -> Self {
+        let bytes = seed.to_be_bytes();
+        let mut mac = [bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]];
+        mac[0] = (mac[0] & !0x1) | 0x2;
+        Self(mac)
+    }
 }
 
 impl Default for MacAddress {
@@ -118,5 +166,37 @@ mod tests {
         assert_eq!(true, mac_address.is_multicast());
         assert_eq!(false, mac_address.is_unicast());
     }
-}
 
+    #[test]
+    fn test_eui64_and_link_local() {
+        // Worked example taken from the standard EUI-64 derivation: the OUI
+        // and NIC halves are split and 0xFF, 0xFE is inserted between them,
+        // then the universal/local bit (bit 1 of the first byte) is
+        // flipped.
+        let mac_address = MacAddress::new([0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E]);
+        assert_eq!(false, mac_address.is_locally_administered());
+        assert_eq!(
+            [0x02, 0x1A, 0x2B, 0xFF, 0xFE, 0x3C, 0x4D, 0x5E],
+            mac_address.to_eui64()
+        );
+        assert_eq!(
+            [
+                0xFE, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x1A, 0x2B, 0xFF, 0xFE, 0x3C,
+                0x4D, 0x5E
+            ],
+            mac_address.to_link_local_ipv6()
+        );
+
+        let locally_administered = MacAddress::new([0x02, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E]);
+        assert_eq!(true, locally_administered.is_locally_administered());
+    }
+
+    #[test]
+    fn test_locally_administered_unicast() {
+        let mac_address = MacAddress::locally_administered_unicast(0x1122_3344_5566_7788);
+        assert_eq!(true, mac_address.is_locally_administered());
+        assert_eq!(true, mac_address.is_unicast());
+        assert_eq!(false, mac_address.is_multicast());
+        assert_eq!(false, mac_address.is_broadcast());
+    }
+}
@@ -20,12 +20,14 @@ impl<'a> Stm32f429ziDefaultPeripherals<'a> {
         exti: &'a crate::exti::Exti<'a>,
         dma1: &'a crate::dma::Dma1<'a>,
         dma2: &'a crate::dma::Dma2<'a>,
+        ethernet_tx_buffers: &'static mut crate::ethernet::TxBuffers,
+        ethernet_rx_buffers: &'static mut crate::ethernet::RxBuffers,
     ) -> Self {
         Self {
             stm32f4: Stm32f4xxDefaultPeripherals::new(rcc, exti, dma1, dma2),
             trng: stm32f4xx::trng::Trng::new(trng_registers::RNG_BASE, rcc),
             can1: stm32f4xx::can::Can::new(rcc, can_registers::CAN1_BASE),
-            ethernet: crate::ethernet::Ethernet::new(rcc),
+            ethernet: crate::ethernet::Ethernet::new(rcc, ethernet_tx_buffers, ethernet_rx_buffers),
         }
     }
     // Necessary for setting up circular dependencies
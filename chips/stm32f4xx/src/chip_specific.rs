@@ -57,7 +57,7 @@ pub mod clock_constants {
     // APB2 frequency limit is twice the APB1 frequency limit
     pub const APB2_FREQUENCY_LIMIT_MHZ: usize = APB1_FREQUENCY_LIMIT_MHZ << 1;
 
-    /// Maximum allowed system clock frequency in MHz
+    /// Maximum allowed system clock frequency in MHz, without overdrive.
     pub const SYS_CLOCK_FREQUENCY_LIMIT_MHZ: usize = if cfg!(any(
         feature = "stm32f410",
         feature = "stm32f411",
@@ -79,13 +79,47 @@ pub mod clock_constants {
         feature = "stm32f469",
         feature = "stm32f479"
     )) {
-        // TODO: Some of these models support overdrive model. Change this constant when overdrive support
-        // is added.
         168
     } else {
         //feature = "stm32f401"
         84
     };
+
+    /// `true` for the models that support overdrive mode, which raises the
+    /// maximum system clock frequency from 168MHz to 180MHz.
+    pub const SUPPORTS_OVERDRIVE: bool = cfg!(any(
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479"
+    ));
+
+    /// Maximum allowed system clock frequency in MHz when overdrive mode is
+    /// enabled. Equal to [`SYS_CLOCK_FREQUENCY_LIMIT_MHZ`] on models that
+    /// don't support overdrive.
+    pub const SYS_CLOCK_FREQUENCY_LIMIT_MHZ_OVERDRIVE: usize = if SUPPORTS_OVERDRIVE {
+        180
+    } else {
+        SYS_CLOCK_FREQUENCY_LIMIT_MHZ
+    };
+
+    /// Supply voltage ranges that affect the number of flash wait states
+    /// required at a given system clock frequency: the lower the supply
+    /// voltage, the more wait states the same frequency needs.
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    pub enum VddRange {
+        /// 1.8V - 2.1V
+        V1_8to2_1,
+        /// 2.1V - 2.4V
+        V2_1to2_4,
+        /// 2.4V - 2.7V
+        V2_4to2_7,
+        /// 2.7V - 3.6V
+        V2_7to3_6,
+    }
 }
 
 /// Chip-specific flash code
@@ -163,19 +197,24 @@ pub mod flash_specific {
     }
 
     // The number of wait cycles depends on two factors: system clock frequency and the supply
-    // voltage. Currently, this method assumes 2.7-3.6V voltage supply (default value).
-    // TODO: Take into the account the power supply
+    // voltage. At a lower supply voltage, the same frequency needs more wait cycles, so the
+    // latency table is shifted by `vdd_range`.
     //
     // The number of wait states varies from chip to chip.
-    pub(crate) fn get_number_wait_cycles_based_on_frequency(frequency_mhz: usize) -> FlashLatency {
-        #[cfg(not(any(
-            feature = "stm32f410",
-            feature = "stm32f411",
-            feature = "stm32f412",
-            feature = "stm32f413",
-            feature = "stm32f423"
-        )))]
+    pub(crate) fn get_number_wait_cycles_based_on_frequency(
+        frequency_mhz: usize,
+        vdd_range: super::clock_constants::VddRange,
+    ) -> FlashLatency {
+        #[cfg(any(
+            feature = "stm32f405",
+            feature = "stm32f415",
+            feature = "stm32f407",
+            feature = "stm32f417"
+        ))]
         {
+            // These models don't expose the lower Vdd ranges, so the
+            // wait-state boundaries stay fixed.
+            let _ = vdd_range;
             if frequency_mhz <= 30 {
                 FlashLatency::Latency0
             } else if frequency_mhz <= 60 {
@@ -190,8 +229,46 @@ pub mod flash_specific {
                 FlashLatency::Latency5
             }
         }
+        #[cfg(not(any(
+            feature = "stm32f405",
+            feature = "stm32f415",
+            feature = "stm32f407",
+            feature = "stm32f417",
+            feature = "stm32f410",
+            feature = "stm32f411",
+            feature = "stm32f412",
+            feature = "stm32f413",
+            feature = "stm32f423"
+        )))]
+        {
+            // Every model not handled above (the F42x/F43x family, and also
+            // F401, F446 and F469/F479), using the F42x/F43x table: the
+            // wait-state boundary (in MHz) shifts with
+            // the supply voltage range; the lower the voltage, the more wait
+            // states the same frequency needs.
+            let step_mhz = match vdd_range {
+                super::clock_constants::VddRange::V2_7to3_6 => 30,
+                super::clock_constants::VddRange::V2_4to2_7 => 24,
+                super::clock_constants::VddRange::V2_1to2_4 => 22,
+                super::clock_constants::VddRange::V1_8to2_1 => 20,
+            };
+
+            match frequency_mhz.saturating_sub(1) / step_mhz {
+                0 => FlashLatency::Latency0,
+                1 => FlashLatency::Latency1,
+                2 => FlashLatency::Latency2,
+                3 => FlashLatency::Latency3,
+                4 => FlashLatency::Latency4,
+                5 => FlashLatency::Latency5,
+                6 => FlashLatency::Latency6,
+                7 => FlashLatency::Latency7,
+                8 => FlashLatency::Latency8,
+                _ => FlashLatency::Latency9,
+            }
+        }
         #[cfg(any(feature = "stm32f410", feature = "stm32f411", feature = "stm32f412"))]
         {
+            let _ = vdd_range;
             if frequency_mhz <= 30 {
                 FlashLatency::Latency0
             } else if frequency_mhz <= 64 {
@@ -204,6 +281,7 @@ pub mod flash_specific {
         }
         #[cfg(any(feature = "stm32f413", feature = "stm32f423"))]
         {
+            let _ = vdd_range;
             if frequency_mhz <= 25 {
                 FlashLatency::Latency0
             } else if frequency_mhz <= 50 {
@@ -261,4 +339,510 @@ pub mod flash_specific {
             _ => FlashLatency::Latency7,
         }
     }
+
+    /// Dual-bank A/B firmware-update support for the F42x/F43x parts, which
+    /// split their flash into two independently programmable/erasable 1MiB
+    /// banks and can boot from either one depending on the `BFB2` option
+    /// bit.
+    ///
+    /// Each bank is expected to carry a [`SlotHeader`] at its very start so
+    /// a minimal boot path (or the board's firmware-update capsule) can
+    /// tell which bank holds the newest valid image, following the A/B
+    /// flashloader pattern.
+    ///
+    /// This module does the real unlocking/erasing/programming/CRC work
+    /// against the STM32F4 flash controller, but it does not itself
+    /// implement `capsules_extra::firmware_update::DualBankFlash` (this
+    /// chip crate cannot depend on that capsules crate). A board's own
+    /// `impl DualBankFlash for ...` one-line-forwards each trait method to
+    /// the function here that does the same job:
+    /// `inactive_bank_capacity` to [`INACTIVE_BANK_CAPACITY`],
+    /// `erase_inactive_bank` to [`erase_bank`], `program_inactive_bank` to
+    /// [`program_bank`], `active_bank_header` to [`read_header`] on
+    /// [`active_boot_bank`], `verify_inactive_bank` to [`verify_bank`],
+    /// `mark_inactive_bank_valid` to [`program_header`], and
+    /// `swap_and_reset` to [`swap_and_reset`], each called with
+    /// `active_boot_bank().inactive()`.
+    pub mod dual_bank {
+        use kernel::utilities::registers::interfaces::{Readable, Writeable};
+        use kernel::utilities::registers::{
+            register_bitfields, register_structs, ReadWrite, WriteOnly,
+        };
+        use kernel::utilities::StaticRef;
+
+        /// Metadata written at the start of each bank so a minimal boot
+        /// path can pick the newest valid image.
+        ///
+        /// This chip crate cannot depend on `capsules_extra` (capsules
+        /// depend on chips, never the reverse), so this is a structurally
+        /// identical copy of `capsules_extra::firmware_update::SlotHeader`:
+        /// same field order, same `SIZE`, same `to_bytes`/`from_bytes`
+        /// wire format. A board wiring this module's functions into that
+        /// capsule's `DualBankFlash` trait just converts between the two
+        /// with a field-by-field copy; keep both definitions in sync if
+        /// the on-flash layout ever changes.
+        #[derive(Copy, Clone, PartialEq, Debug)]
+        pub struct SlotHeader {
+            /// Length in bytes of the staged image.
+            pub image_length: u32,
+            /// CRC32 of the staged image, used to detect a torn write.
+            pub crc32: u32,
+            /// Monotonically increasing version, used to pick the newest
+            /// valid image when both banks are valid.
+            pub version: u32,
+            /// Whether this header (and the image it describes) should be
+            /// trusted.
+            pub valid: bool,
+        }
+
+        impl SlotHeader {
+            /// Size in bytes of the serialized header.
+            pub const SIZE: usize = 16;
+
+            const VALID_MAGIC: u32 = 0x5343_4B31;
+
+            /// Builds a header for a freshly staged, valid image.
+            pub fn new(image_length: u32, crc32: u32, version: u32) -> Self {
+                Self {
+                    image_length,
+                    crc32,
+                    version,
+                    valid: true,
+                }
+            }
+
+            /// Serializes this header the way it is written at the start
+            /// of a bank.
+            pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+                let mut bytes = [0; Self::SIZE];
+                bytes[0..4].copy_from_slice(&self.image_length.to_le_bytes());
+                bytes[4..8].copy_from_slice(&self.crc32.to_le_bytes());
+                bytes[8..12].copy_from_slice(&self.version.to_le_bytes());
+                let valid_marker = if self.valid { Self::VALID_MAGIC } else { 0 };
+                bytes[12..16].copy_from_slice(&valid_marker.to_le_bytes());
+                bytes
+            }
+
+            /// Parses a header previously written by
+            /// [`SlotHeader::to_bytes`].
+            pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Self {
+                Self {
+                    image_length: u32::from_le_bytes(bytes[0..4].try_into().unwrap()),
+                    crc32: u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+                    version: u32::from_le_bytes(bytes[8..12].try_into().unwrap()),
+                    valid: u32::from_le_bytes(bytes[12..16].try_into().unwrap())
+                        == Self::VALID_MAGIC,
+                }
+            }
+        }
+
+        /// Size in bytes of each physical flash bank.
+        pub const BANK_SIZE: usize = 0x10_0000;
+        /// Base address of bank 1.
+        pub const BANK1_BASE: usize = 0x0800_0000;
+        /// Base address of bank 2.
+        pub const BANK2_BASE: usize = 0x0810_0000;
+
+        /// Bytes reserved for a [`SlotHeader`] at the very start of each
+        /// bank; image data staged by [`program_bank`] starts right after.
+        pub const DATA_OFFSET: usize = SlotHeader::SIZE;
+
+        /// Usable capacity for staged image data in a single bank, i.e.
+        /// [`BANK_SIZE`] minus the space reserved for the header.
+        pub const INACTIVE_BANK_CAPACITY: usize = BANK_SIZE - DATA_OFFSET;
+
+        /// One of the two physical flash banks.
+        #[derive(Copy, Clone, PartialEq, Debug)]
+        pub enum FlashBank {
+            /// First 1MiB bank, `0x0800_0000`
+            Bank1,
+            /// Second 1MiB bank, `0x0810_0000`
+            Bank2,
+        }
+
+        impl FlashBank {
+            /// Base address of this bank.
+            pub const fn base_address(self) -> usize {
+                match self {
+                    FlashBank::Bank1 => BANK1_BASE,
+                    FlashBank::Bank2 => BANK2_BASE,
+                }
+            }
+
+            /// The bank that isn't `self`, i.e. the one a firmware update
+            /// should be staged into.
+            pub const fn inactive(self) -> FlashBank {
+                match self {
+                    FlashBank::Bank1 => FlashBank::Bank2,
+                    FlashBank::Bank2 => FlashBank::Bank1,
+                }
+            }
+
+            /// Index of this bank's first erase sector in `SNB`: bank 2's
+            /// sectors are simply numbered [`SECTOR_SIZES_BYTES`]`.len()`
+            /// higher than bank 1's matching sector.
+            const fn first_sector(self) -> u32 {
+                match self {
+                    FlashBank::Bank1 => 0,
+                    FlashBank::Bank2 => SECTOR_SIZES_BYTES.len() as u32,
+                }
+            }
+        }
+
+        /// Byte sizes of the 12 erase sectors making up a single 1MiB bank,
+        /// in address order (both banks share this layout).
+        const SECTOR_SIZES_BYTES: [usize; 12] = [
+            16 * 1024,
+            16 * 1024,
+            16 * 1024,
+            16 * 1024,
+            64 * 1024,
+            128 * 1024,
+            128 * 1024,
+            128 * 1024,
+            128 * 1024,
+            128 * 1024,
+            128 * 1024,
+            128 * 1024,
+        ];
+
+        const FLASH_BASE: StaticRef<FlashRegisters> =
+            unsafe { StaticRef::new(0x4002_3C00 as *const FlashRegisters) };
+
+        register_structs! {
+            FlashRegisters {
+                (0x00 => _reserved0),
+                (0x04 => keyr: WriteOnly<u32>),
+                (0x08 => optkeyr: WriteOnly<u32>),
+                (0x0C => sr: ReadWrite<u32, SR::Register>),
+                (0x10 => cr: ReadWrite<u32, CR::Register>),
+                (0x14 => optcr: ReadWrite<u32, OPTCR::Register>),
+                (0x18 => @END),
+            }
+        }
+
+        register_bitfields![u32,
+            SR [
+                EOP OFFSET(0) NUMBITS(1) [],
+                WRPERR OFFSET(4) NUMBITS(1) [],
+                PGAERR OFFSET(5) NUMBITS(1) [],
+                PGPERR OFFSET(6) NUMBITS(1) [],
+                PGSERR OFFSET(7) NUMBITS(1) [],
+                BSY OFFSET(16) NUMBITS(1) [],
+            ],
+            CR [
+                PG OFFSET(0) NUMBITS(1) [],
+                SER OFFSET(1) NUMBITS(1) [],
+                MER OFFSET(2) NUMBITS(1) [],
+                SNB OFFSET(3) NUMBITS(5) [],
+                PSIZE OFFSET(8) NUMBITS(2) [],
+                STRT OFFSET(16) NUMBITS(1) [],
+                LOCK OFFSET(31) NUMBITS(1) [],
+            ],
+            OPTCR [
+                OPTLOCK OFFSET(0) NUMBITS(1) [],
+                OPTSTRT OFFSET(1) NUMBITS(1) [],
+                BFB2 OFFSET(4) NUMBITS(1) [],
+            ],
+        ];
+
+        const FLASH_KEY1: u32 = 0x4567_0123;
+        const FLASH_KEY2: u32 = 0xCDEF_89AB;
+        const OPT_KEY1: u32 = 0x0819_2A3B;
+        const OPT_KEY2: u32 = 0x4C5D_6E7F;
+
+        /// `PSIZE` value for byte-wide (8-bit) programming, which this
+        /// module uses throughout so it never has to worry about alignment
+        /// of the capsule-supplied chunk boundaries it is asked to write.
+        const PSIZE_BYTE: u32 = 0b00;
+
+        /// Clears `CR`'s `LOCK` bit if it is set, so `CR` can be written.
+        fn unlock_cr() {
+            let flash = FLASH_BASE;
+            if flash.cr.is_set(CR::LOCK) {
+                flash.keyr.set(FLASH_KEY1);
+                flash.keyr.set(FLASH_KEY2);
+            }
+        }
+
+        /// Sets `CR`'s `LOCK` bit, so `CR` cannot be written again until
+        /// [`unlock_cr`] runs.
+        fn lock_cr() {
+            FLASH_BASE.cr.modify(CR::LOCK::SET);
+        }
+
+        /// Blocks until the current erase/program operation completes,
+        /// clears whatever status bits it set, and turns any error flag
+        /// into `Err`.
+        fn wait_until_idle() -> Result<(), kernel::ErrorCode> {
+            let flash = FLASH_BASE;
+            while flash.sr.is_set(SR::BSY) {}
+
+            let errored = flash.sr.is_set(SR::WRPERR)
+                || flash.sr.is_set(SR::PGAERR)
+                || flash.sr.is_set(SR::PGPERR)
+                || flash.sr.is_set(SR::PGSERR);
+
+            // SR's status/error bits are write-1-to-clear.
+            flash.sr.set(flash.sr.get());
+
+            if errored {
+                Err(kernel::ErrorCode::FAIL)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Erases every sector making up `bank`, leaving its entire 1MiB
+        /// region (header and data alike) as `0xFF`.
+        pub fn erase_bank(bank: FlashBank) -> Result<(), kernel::ErrorCode> {
+            let flash = FLASH_BASE;
+            unlock_cr();
+
+            let first_sector = bank.first_sector();
+            let mut result = Ok(());
+            for sector in first_sector..first_sector + SECTOR_SIZES_BYTES.len() as u32 {
+                flash
+                    .cr
+                    .modify(CR::SER::SET + CR::SNB.val(sector) + CR::PSIZE.val(PSIZE_BYTE));
+                flash.cr.modify(CR::STRT::SET);
+                result = wait_until_idle();
+                flash.cr.modify(CR::SER::CLEAR);
+                if result.is_err() {
+                    break;
+                }
+            }
+
+            lock_cr();
+            result
+        }
+
+        /// Programs `data` at `offset` bytes into `bank`'s data region
+        /// (i.e. `bank`'s base address plus [`DATA_OFFSET`]), one byte at a
+        /// time.
+        pub fn program_bank(
+            bank: FlashBank,
+            offset: usize,
+            data: &[u8],
+        ) -> Result<(), kernel::ErrorCode> {
+            if offset > INACTIVE_BANK_CAPACITY || data.len() > INACTIVE_BANK_CAPACITY - offset {
+                return Err(kernel::ErrorCode::SIZE);
+            }
+
+            let address = bank.base_address() + DATA_OFFSET + offset;
+            program_bytes(address, data)
+        }
+
+        /// Writes `header` at the very start of `bank`.
+        pub fn program_header(
+            bank: FlashBank,
+            header: &SlotHeader,
+        ) -> Result<(), kernel::ErrorCode> {
+            program_bytes(bank.base_address(), &header.to_bytes())
+        }
+
+        fn program_bytes(address: usize, data: &[u8]) -> Result<(), kernel::ErrorCode> {
+            let flash = FLASH_BASE;
+            unlock_cr();
+            flash.cr.modify(CR::PSIZE.val(PSIZE_BYTE));
+
+            let base = address as *mut u8;
+            let mut result = Ok(());
+            for (i, &byte) in data.iter().enumerate() {
+                flash.cr.modify(CR::PG::SET);
+                // SAFETY: `address` plus `data.len()` was checked by the
+                // caller to stay within a single bank, which this module
+                // reserves for exclusive use by flash programming.
+                unsafe { core::ptr::write_volatile(base.add(i), byte) };
+                result = wait_until_idle();
+                flash.cr.modify(CR::PG::CLEAR);
+                if result.is_err() {
+                    break;
+                }
+            }
+
+            lock_cr();
+            result
+        }
+
+        /// Reads `bank`'s header, if its magic marks it valid.
+        pub fn read_header(bank: FlashBank) -> Option<SlotHeader> {
+            let base = bank.base_address() as *const u8;
+            let mut bytes = [0u8; SlotHeader::SIZE];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                // SAFETY: every bank is at least `SlotHeader::SIZE` bytes
+                // long, and flash is always readable.
+                *byte = unsafe { core::ptr::read_volatile(base.add(i)) };
+            }
+
+            let header = SlotHeader::from_bytes(&bytes);
+            if header.valid {
+                Some(header)
+            } else {
+                None
+            }
+        }
+
+        /// Recomputes the CRC32 over the first `length` bytes of `bank`'s
+        /// data region and reports whether it matches `expected_crc32`.
+        pub fn verify_bank(
+            bank: FlashBank,
+            length: usize,
+            expected_crc32: u32,
+        ) -> Result<bool, kernel::ErrorCode> {
+            if length > INACTIVE_BANK_CAPACITY {
+                return Err(kernel::ErrorCode::SIZE);
+            }
+
+            let base = (bank.base_address() + DATA_OFFSET) as *const u8;
+            // SAFETY: `length` was just checked against the bank's data
+            // capacity, and flash is always readable.
+            let data = unsafe { core::slice::from_raw_parts(base, length) };
+            Ok(crc32(data) == expected_crc32)
+        }
+
+        /// Software CRC32 (the IEEE 802.3 / zlib variant, polynomial
+        /// `0xEDB8_8320`), matching whatever the board's update tooling
+        /// used to compute the `crc32` it asks [`verify_bank`] to check.
+        fn crc32(data: &[u8]) -> u32 {
+            let mut crc = 0xFFFF_FFFFu32;
+            for &byte in data {
+                crc ^= byte as u32;
+                for _ in 0..8 {
+                    let mask = (crc & 1).wrapping_neg();
+                    crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+                }
+            }
+            !crc
+        }
+
+        /// Reads the bank the bootloader will boot from at the next reset.
+        pub fn active_boot_bank() -> FlashBank {
+            if FLASH_BASE.optcr.is_set(OPTCR::BFB2) {
+                FlashBank::Bank2
+            } else {
+                FlashBank::Bank1
+            }
+        }
+
+        /// Selects `bank` to boot from at the next reset, by setting or
+        /// clearing the `BFB2` option bit and committing the option bytes.
+        /// This only takes effect after a reset.
+        pub fn set_boot_bank(bank: FlashBank) {
+            let flash = FLASH_BASE;
+
+            if flash.optcr.is_set(OPTCR::OPTLOCK) {
+                flash.optkeyr.set(OPT_KEY1);
+                flash.optkeyr.set(OPT_KEY2);
+            }
+
+            match bank {
+                FlashBank::Bank2 => flash.optcr.modify(OPTCR::BFB2::SET),
+                FlashBank::Bank1 => flash.optcr.modify(OPTCR::BFB2::CLEAR),
+            }
+            flash.optcr.modify(OPTCR::OPTSTRT::SET);
+        }
+
+        const SCB_BASE: StaticRef<ScbRegisters> =
+            unsafe { StaticRef::new(0xE000_ED0C as *const ScbRegisters) };
+
+        register_structs! {
+            ScbRegisters {
+                (0x00 => aircr: ReadWrite<u32, AIRCR::Register>),
+                (0x04 => @END),
+            }
+        }
+
+        register_bitfields![u32,
+            AIRCR [
+                VECTKEY OFFSET(16) NUMBITS(16) [],
+                SYSRESETREQ OFFSET(2) NUMBITS(1) [],
+            ],
+        ];
+
+        /// Selects `bank` to boot from at the next reset and resets the
+        /// device immediately, via the Cortex-M `SCB.AIRCR` register.
+        /// Never returns.
+        pub fn swap_and_reset(bank: FlashBank) -> ! {
+            set_boot_bank(bank);
+            SCB_BASE
+                .aircr
+                .write(AIRCR::VECTKEY.val(0x05FA) + AIRCR::SYSRESETREQ::SET);
+            loop {}
+        }
+    }
+}
+
+/// Chip-specific overdrive support.
+///
+/// Overdrive raises the maximum system clock frequency from 168MHz to
+/// 180MHz on the models listed in [`clock_constants::SUPPORTS_OVERDRIVE`].
+/// It must be enabled before the system clock is switched above 168MHz, and
+/// is controlled entirely through the `PWR` peripheral.
+pub mod overdrive {
+    use kernel::utilities::registers::interfaces::{Readable, Writeable};
+    use kernel::utilities::registers::{register_bitfields, register_structs, ReadWrite};
+    use kernel::utilities::StaticRef;
+
+    const PWR_BASE: StaticRef<PwrRegisters> =
+        unsafe { StaticRef::new(0x4000_7000 as *const PwrRegisters) };
+
+    register_structs! {
+        PwrRegisters {
+            (0x00 => cr: ReadWrite<u32, PWR_CR::Register>),
+            (0x04 => csr: ReadWrite<u32, PWR_CSR::Register>),
+            (0x08 => @END),
+        }
+    }
+
+    register_bitfields![u32,
+        PWR_CR [
+            ODEN OFFSET(16) NUMBITS(1) [],
+            ODSWEN OFFSET(17) NUMBITS(1) [],
+        ],
+        PWR_CSR [
+            ODRDY OFFSET(16) NUMBITS(1) [],
+            ODSWRDY OFFSET(17) NUMBITS(1) [],
+        ],
+    ];
+
+    /// Enables overdrive mode, blocking until the hardware confirms the
+    /// switch. Must be called before raising the system clock above
+    /// 168MHz, and only on chips where
+    /// [`super::clock_constants::SUPPORTS_OVERDRIVE`] is `true`.
+    fn enable() {
+        let pwr = PWR_BASE;
+
+        pwr.cr.modify(PWR_CR::ODEN::SET);
+        while !pwr.csr.is_set(PWR_CSR::ODRDY) {}
+
+        pwr.cr.modify(PWR_CR::ODSWEN::SET);
+        while !pwr.csr.is_set(PWR_CSR::ODSWRDY) {}
+    }
+
+    /// Enables overdrive mode if `target_frequency_mhz` needs it, blocking
+    /// until the hardware confirms the switch. This is the integration
+    /// point the RCC system-clock switch path must call before writing a
+    /// PLL configuration above
+    /// [`super::clock_constants::SYS_CLOCK_FREQUENCY_LIMIT_MHZ`] into
+    /// `RCC_CFGR`: overdrive has to be enabled and ready *before* the
+    /// higher frequency is selected, never after. Returns `Err(())` if
+    /// `target_frequency_mhz` is unreachable on this chip, i.e. it exceeds
+    /// [`super::clock_constants::SYS_CLOCK_FREQUENCY_LIMIT_MHZ_OVERDRIVE`].
+    pub fn enable_if_needed(target_frequency_mhz: usize) -> Result<(), ()> {
+        use super::clock_constants::{
+            SUPPORTS_OVERDRIVE, SYS_CLOCK_FREQUENCY_LIMIT_MHZ,
+            SYS_CLOCK_FREQUENCY_LIMIT_MHZ_OVERDRIVE,
+        };
+
+        if target_frequency_mhz <= SYS_CLOCK_FREQUENCY_LIMIT_MHZ {
+            return Ok(());
+        }
+        if !SUPPORTS_OVERDRIVE || target_frequency_mhz > SYS_CLOCK_FREQUENCY_LIMIT_MHZ_OVERDRIVE {
+            return Err(());
+        }
+
+        enable();
+        Ok(())
+    }
 }